@@ -1,6 +1,6 @@
 use crate::errors::{GrimpError, GrimpResult};
-use crate::filesystem::get_file_system_boxed;
-use crate::import_scanning::{DirectImport, imports_by_module_to_py};
+use crate::filesystem::{FileSystem, get_file_system_boxed};
+use crate::import_scanning::{DirectImport, ImportCategory, imports_by_module_to_py};
 use crate::module_finding::Module;
 use pyo3::types::PyAnyMethods;
 use pyo3::types::{PyDict, PySet};
@@ -82,19 +82,11 @@ fn imports_by_module_to_rust(
 fn serialize_imports_by_module(
     imports_by_module: &HashMap<Module, HashSet<DirectImport>>,
 ) -> String {
-    let raw_map: HashMap<&str, Vec<(&str, usize, &str)>> = imports_by_module
+    let raw_map: HashMap<&str, Vec<RawImport>> = imports_by_module
         .iter()
         .map(|(module, imports)| {
-            let imports_vec: Vec<(&str, usize, &str)> = imports
-                .iter()
-                .map(|import| {
-                    (
-                        import.imported.as_str(),
-                        import.line_number,
-                        import.line_contents.as_str(),
-                    )
-                })
-                .collect();
+            let imports_vec: Vec<RawImport> =
+                imports.iter().map(RawImport::from_direct_import).collect();
             (module.name.as_str(), imports_vec)
         })
         .collect();
@@ -102,11 +94,104 @@ fn serialize_imports_by_module(
     serde_json::to_string(&raw_map).expect("Failed to serialize to JSON")
 }
 
+/// A single serialized import. `Aliased` is the layout written now; `Dynamic`,
+/// `Categorised` and `Legacy` are the shorter layouts of older caches, down to
+/// the one written before import categorisation. Legacy entries are read back as third-party, matching the old behaviour
+/// where every external import shared a single bucket.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum RawImport {
+    // (imported, category, is_dynamic, full_name, alias, line_number, line_contents)
+    Aliased(String, String, bool, String, Option<String>, usize, String),
+    Dynamic(String, String, bool, usize, String),
+    Categorised(String, String, usize, String),
+    Legacy(String, usize, String),
+}
+
+impl RawImport {
+    /// Rebuild a `DirectImport` from its serialized form, filling in defaults
+    /// for fields absent from older cache layouts.
+    fn to_direct_import(&self, importer: &str) -> DirectImport {
+        // Fields absent from older layouts get sensible defaults: `full_name`
+        // falls back to the (distilled) imported name, and an import with no
+        // stored alias is treated as unaliased.
+        let (imported, category, is_dynamic, full_name, alias, line_number, line_contents) =
+            match self {
+                RawImport::Aliased(
+                    imported,
+                    category,
+                    is_dynamic,
+                    full_name,
+                    alias,
+                    line_number,
+                    line_contents,
+                ) => (
+                    imported.clone(),
+                    ImportCategory::from_str(category),
+                    *is_dynamic,
+                    full_name.clone(),
+                    alias.clone(),
+                    *line_number,
+                    line_contents.clone(),
+                ),
+                RawImport::Dynamic(imported, category, is_dynamic, line_number, line_contents) => (
+                    imported.clone(),
+                    ImportCategory::from_str(category),
+                    *is_dynamic,
+                    imported.clone(),
+                    None,
+                    *line_number,
+                    line_contents.clone(),
+                ),
+                RawImport::Categorised(imported, category, line_number, line_contents) => (
+                    imported.clone(),
+                    ImportCategory::from_str(category),
+                    false,
+                    imported.clone(),
+                    None,
+                    *line_number,
+                    line_contents.clone(),
+                ),
+                RawImport::Legacy(imported, line_number, line_contents) => (
+                    imported.clone(),
+                    ImportCategory::ThirdParty,
+                    false,
+                    imported.clone(),
+                    None,
+                    *line_number,
+                    line_contents.clone(),
+                ),
+            };
+        DirectImport {
+            importer: importer.to_string(),
+            imported,
+            category,
+            is_dynamic,
+            full_name,
+            alias,
+            line_number,
+            line_contents,
+        }
+    }
+
+    fn from_direct_import(import: &DirectImport) -> RawImport {
+        RawImport::Aliased(
+            import.imported.clone(),
+            import.category.as_str().to_string(),
+            import.is_dynamic,
+            import.full_name.clone(),
+            import.alias.clone(),
+            import.line_number,
+            import.line_contents.clone(),
+        )
+    }
+}
+
 pub fn parse_json_to_map(
     json_str: &str,
     filename: &str,
 ) -> GrimpResult<HashMap<Module, HashSet<DirectImport>>> {
-    let raw_map: HashMap<String, Vec<(String, usize, String)>> = serde_json::from_str(json_str)
+    let raw_map: HashMap<String, Vec<RawImport>> = serde_json::from_str(json_str)
         .map_err(|_| GrimpError::CorruptCache(filename.to_string()))?;
 
     let mut parsed_map: HashMap<Module, HashSet<DirectImport>> = HashMap::new();
@@ -117,15 +202,283 @@ pub fn parse_json_to_map(
         };
         let import_set: HashSet<DirectImport> = imports
             .into_iter()
-            .map(|(imported, line_number, line_contents)| DirectImport {
-                importer: module_name.clone(),
-                imported,
-                line_number,
-                line_contents,
-            })
+            .map(|raw| raw.to_direct_import(&module_name))
             .collect();
         parsed_map.insert(module, import_set);
     }
 
     Ok(parsed_map)
 }
+
+/// Version of the incremental, per-module cache format. Bumped whenever the
+/// on-disk layout changes so that an older file is treated as a
+/// [`GrimpError::CorruptCache`] and falls back to a clean rescan rather than
+/// being misread.
+const INCREMENTAL_CACHE_VERSION: u32 = 1;
+
+/// The incremental cache file: a version tag and a per-module record keyed by
+/// module name. Each record stores a content hash of the module's source
+/// alongside its serialized imports, so a module whose source is unchanged can
+/// be reused without re-parsing it.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct IncrementalCache {
+    version: u32,
+    /// Fingerprint of the scan options the cache was built with (dynamic-import
+    /// scanning, external packages, type-checking exclusion, target version).
+    /// The per-module hash only tracks source changes, so a run with different
+    /// options must not reuse these entries; a mismatch invalidates the whole
+    /// file just like a stale `version`.
+    config: String,
+    modules: HashMap<String, CachedModule>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CachedModule {
+    hash: String,
+    imports: Vec<RawImport>,
+}
+
+/// The outcome of reading an incremental cache against the current module set:
+/// the imports that could be reused as-is, plus the split of which modules were
+/// reused and which still need rescanning by the caller.
+pub struct CacheReadOutcome {
+    pub imports_by_module: HashMap<Module, HashSet<DirectImport>>,
+    pub reused: HashSet<Module>,
+    pub rescanned: HashSet<Module>,
+}
+
+/// A cheap, non-cryptographic hash of a module's (decoded) source, used only to
+/// decide whether the file changed since the cache was written.
+fn content_hash(contents: &str) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    contents.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Read the incremental cache at `filename` and decide, per module, which
+/// cached import sets are still valid.
+///
+/// A module is reused when it has a cache record whose stored content hash
+/// matches its source file as it is now; otherwise it is reported as needing a
+/// rescan, as is a module whose source file can't be read. Modules absent from
+/// `module_filenames` are dropped. A missing or unparseable cache file, a
+/// stale/unknown format version, or a `config_fingerprint`
+/// that doesn't match the one the cache was written with surfaces as
+/// [`GrimpError::CorruptCache`] so the caller can fall back to a full rescan.
+#[allow(clippy::borrowed_box)]
+pub fn read_cache(
+    filename: &str,
+    module_filenames: &HashMap<Module, String>,
+    config_fingerprint: &str,
+    file_system: &Box<dyn FileSystem + Send + Sync>,
+) -> GrimpResult<CacheReadOutcome> {
+    let file_contents = file_system
+        .read(filename)
+        .map_err(|_| GrimpError::CorruptCache(filename.to_string()))?;
+    let cache: IncrementalCache = serde_json::from_str(&file_contents)
+        .map_err(|_| GrimpError::CorruptCache(filename.to_string()))?;
+
+    if cache.version != INCREMENTAL_CACHE_VERSION || cache.config != config_fingerprint {
+        return Err(GrimpError::CorruptCache(filename.to_string()));
+    }
+
+    let mut imports_by_module = HashMap::new();
+    let mut reused = HashSet::new();
+    let mut rescanned = HashSet::new();
+
+    for (module, module_filename) in module_filenames {
+        let cached = cache.modules.get(&module.name);
+        let current_hash = file_system.read(module_filename).ok().map(|c| content_hash(&c));
+        match (cached, current_hash) {
+            (Some(cached), Some(current_hash)) if cached.hash == current_hash => {
+                let import_set: HashSet<DirectImport> = cached
+                    .imports
+                    .iter()
+                    .map(|raw| raw.to_direct_import(&module.name))
+                    .collect();
+                imports_by_module.insert(module.clone(), import_set);
+                reused.insert(module.clone());
+            }
+            _ => {
+                rescanned.insert(module.clone());
+            }
+        }
+    }
+
+    Ok(CacheReadOutcome {
+        imports_by_module,
+        reused,
+        rescanned,
+    })
+}
+
+/// Write the incremental cache, recording each module's content hash alongside
+/// its imports so the next run can skip unchanged modules. `imports_by_module`
+/// need not cover every module in `module_filenames`; a module with no entry is
+/// cached as having no imports.
+#[allow(clippy::borrowed_box)]
+pub fn write_cache(
+    filename: &str,
+    module_filenames: &HashMap<Module, String>,
+    imports_by_module: &HashMap<Module, HashSet<DirectImport>>,
+    config_fingerprint: &str,
+    file_system: &mut Box<dyn FileSystem + Send + Sync>,
+) -> PyResult<()> {
+    let mut modules = HashMap::new();
+    for (module, module_filename) in module_filenames {
+        let contents = file_system.read(module_filename)?;
+        let imports = imports_by_module
+            .get(module)
+            .map(|imports| imports.iter().map(RawImport::from_direct_import).collect())
+            .unwrap_or_default();
+        modules.insert(
+            module.name.clone(),
+            CachedModule {
+                hash: content_hash(&contents),
+                imports,
+            },
+        );
+    }
+
+    let cache = IncrementalCache {
+        version: INCREMENTAL_CACHE_VERSION,
+        config: config_fingerprint.to_string(),
+        modules,
+    };
+    let file_contents = serde_json::to_string(&cache).expect("Failed to serialize to JSON");
+    file_system.write(filename, &file_contents)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filesystem::FakeBasicFileSystem;
+
+    fn import(importer: &str, imported: &str) -> DirectImport {
+        DirectImport {
+            importer: importer.to_string(),
+            imported: imported.to_string(),
+            category: ImportCategory::FirstParty,
+            is_dynamic: false,
+            full_name: imported.to_string(),
+            alias: None,
+            line_number: 1,
+            line_contents: format!("from . import {imported}"),
+        }
+    }
+
+    const FINGERPRINT: &str = "false|true|false|3.12";
+
+    /// The incremental cache file for a single module whose source hashes to the
+    /// given `source`, recording one import.
+    fn cache_json(module_name: &str, source: &str, imported: &str) -> String {
+        let mut modules = HashMap::new();
+        modules.insert(
+            module_name.to_string(),
+            CachedModule {
+                hash: content_hash(source),
+                imports: vec![RawImport::from_direct_import(&import(module_name, imported))],
+            },
+        );
+        let cache = IncrementalCache {
+            version: INCREMENTAL_CACHE_VERSION,
+            config: FINGERPRINT.to_string(),
+            modules,
+        };
+        serde_json::to_string(&cache).unwrap()
+    }
+
+    fn fake(content: HashMap<String, String>) -> Box<dyn FileSystem + Send + Sync> {
+        Box::new(FakeBasicFileSystem::new(None, Some(content)).unwrap())
+    }
+
+    #[test]
+    fn test_serialize_parse_round_trip() {
+        let module = Module {
+            name: "pkg.a".to_string(),
+        };
+        let imports = HashSet::from([import("pkg.a", "pkg.b")]);
+        let mut by_module = HashMap::new();
+        by_module.insert(module, imports);
+
+        let json = serialize_imports_by_module(&by_module);
+        let parsed = parse_json_to_map(&json, "cache.json").unwrap();
+
+        assert_eq!(parsed, by_module);
+    }
+
+    #[test]
+    fn test_read_cache_reuses_unchanged_module() {
+        let source = "from . import b";
+        let content = HashMap::from([
+            ("cache.json".to_string(), cache_json("pkg.a", source, "pkg.b")),
+            ("pkg/a.py".to_string(), source.to_string()),
+        ]);
+        let file_system = fake(content);
+
+        let module = Module {
+            name: "pkg.a".to_string(),
+        };
+        let module_filenames = HashMap::from([(module.clone(), "pkg/a.py".to_string())]);
+
+        let outcome =
+            read_cache("cache.json", &module_filenames, FINGERPRINT, &file_system).unwrap();
+
+        assert!(outcome.reused.contains(&module));
+        assert!(outcome.rescanned.is_empty());
+        assert_eq!(outcome.imports_by_module[&module], HashSet::from([import("pkg.a", "pkg.b")]));
+    }
+
+    #[test]
+    fn test_read_cache_rescans_changed_module() {
+        let content = HashMap::from([
+            (
+                "cache.json".to_string(),
+                cache_json("pkg.a", "from . import b", "pkg.b"),
+            ),
+            ("pkg/a.py".to_string(), "from . import c".to_string()),
+        ]);
+        let file_system = fake(content);
+
+        let module = Module {
+            name: "pkg.a".to_string(),
+        };
+        let module_filenames = HashMap::from([(module.clone(), "pkg/a.py".to_string())]);
+
+        let outcome =
+            read_cache("cache.json", &module_filenames, FINGERPRINT, &file_system).unwrap();
+
+        assert!(outcome.rescanned.contains(&module));
+        assert!(outcome.reused.is_empty());
+        assert!(outcome.imports_by_module.is_empty());
+    }
+
+    #[test]
+    fn test_read_cache_invalidated_by_config_change() {
+        let source = "from . import b";
+        let content = HashMap::from([
+            ("cache.json".to_string(), cache_json("pkg.a", source, "pkg.b")),
+            ("pkg/a.py".to_string(), source.to_string()),
+        ]);
+        let file_system = fake(content);
+
+        let module = Module {
+            name: "pkg.a".to_string(),
+        };
+        let module_filenames = HashMap::from([(module, "pkg/a.py".to_string())]);
+
+        // Even though the source is unchanged, a different scan-option
+        // fingerprint must invalidate the whole cache.
+        let outcome = read_cache(
+            "cache.json",
+            &module_filenames,
+            "true|true|false|3.12",
+            &file_system,
+        );
+
+        assert!(matches!(outcome, Err(GrimpError::CorruptCache(_))));
+    }
+}
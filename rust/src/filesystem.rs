@@ -1,10 +1,11 @@
-use pyo3::exceptions::{PyFileNotFoundError, PyUnicodeDecodeError};
+use pyo3::exceptions::{PyFileNotFoundError, PyUnicodeDecodeError, PyValueError};
 use pyo3::prelude::*;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use unindent::unindent;
 
 pub trait FileSystem: Send + Sync {
@@ -17,6 +18,164 @@ pub trait FileSystem: Send + Sync {
     fn exists(&self, file_name: &str) -> bool;
 
     fn read(&self, file_name: &str) -> PyResult<String>;
+
+    /// The names of the entries (files and directories) directly within `dir`,
+    /// like `os.listdir`.
+    fn listdir(&self, dir: &str) -> PyResult<Vec<String>>;
+
+    /// Recursively walk `root`, yielding `(dirpath, subdir_names, file_names)`
+    /// tuples top-down, like `os.walk`. Symlink loops are guarded against so a
+    /// self-referential directory can't cause infinite recursion.
+    fn walk(&self, root: &str) -> PyResult<Vec<(String, Vec<String>, Vec<String>)>>;
+
+    /// The character separating a filename from its extension, like
+    /// `os.extsep` (normally `"."`).
+    fn extsep(&self) -> String {
+        ".".to_string()
+    }
+
+    /// The alternate path separator, like `os.altsep`, or `None` when there
+    /// isn't one. Any occurrence of it is folded into [`FileSystem::sep`] before
+    /// a path is split or joined.
+    fn altsep(&self) -> Option<String> {
+        None
+    }
+
+    /// Translate any alternate separator in `path` to the primary separator, so
+    /// a path that didn't originate on this filesystem (e.g. a Windows-style
+    /// `pkg\mod.py` handed to a `/`-based filesystem) splits correctly.
+    fn normalize_separators(&self, path: &str) -> String {
+        match self.altsep() {
+            Some(altsep) => path.replace(&altsep, &self.sep()),
+            None => path.to_string(),
+        }
+    }
+
+    /// Split `name` into `(root, ext)`, like `os.path.splitext`: `ext` is the
+    /// trailing extension including its separator, or empty. A leading
+    /// separator (a dotfile such as `.gitignore`) is not treated as an
+    /// extension.
+    fn splitext(&self, name: &str) -> (String, String) {
+        let extsep = self.extsep();
+        let sep = self.sep();
+        // Only the final path component can carry an extension.
+        let basename_start = name.rfind(&sep).map(|i| i + sep.len()).unwrap_or(0);
+        let basename = &name[basename_start..];
+        // Leading extseps belong to the root (dotfiles), not the extension.
+        let leading = basename.len() - basename.trim_start_matches(extsep.as_str()).len();
+        match basename[leading..].rfind(&extsep) {
+            Some(dot) => {
+                let split_at = basename_start + leading + dot;
+                (name[..split_at].to_string(), name[split_at..].to_string())
+            }
+            None => (name.to_string(), String::new()),
+        }
+    }
+}
+
+/// Decode the bytes of a Python source file into a string, honouring a PEP 263
+/// coding declaration in the first two lines and defaulting to UTF-8 otherwise.
+///
+/// Shared by every [`FileSystem`] implementation that reads `.py` sources, so
+/// that files served out of an archive decode exactly as on-disk ones do.
+///
+/// This routine was authored primarily by an LLM.
+pub fn decode_python_source(bytes: &[u8], file_name: &str) -> PyResult<String> {
+    // Python files are assumed UTF-8 by default (PEP 686), but they can specify an alternative
+    // encoding, which we need to take into account here.
+    // See https://peps.python.org/pep-0263/
+
+    let s = String::from_utf8_lossy(bytes);
+    let encoding_re = Regex::new(r"^[ \t\f]*#.*?coding[:=][ \t]*([-_.a-zA-Z0-9]+)").unwrap();
+
+    let mut detected_encoding: Option<String> = None;
+
+    // Coding specification needs to be in the first two lines, or it's ignored.
+    for line in s.lines().take(2) {
+        if let Some(captures) = encoding_re.captures(line)
+            && let Some(encoding_name) = captures.get(1) {
+                detected_encoding = Some(encoding_name.as_str().to_string());
+                break;
+            }
+    }
+
+    if let Some(enc_name) = detected_encoding {
+        let encoding = encoding_rs::Encoding::for_label(enc_name.as_bytes()).ok_or_else(|| {
+            PyUnicodeDecodeError::new_err(format!(
+                "Failed to decode file {file_name} (unknown encoding '{enc_name}')"
+            ))
+        })?;
+        let (decoded_s, _, had_errors) = encoding.decode(bytes);
+        if had_errors {
+            Err(PyUnicodeDecodeError::new_err(format!(
+                "Failed to decode file {file_name} with encoding '{enc_name}'"
+            )))
+        } else {
+            Ok(decoded_s.into_owned())
+        }
+    } else {
+        // Default to UTF-8 if no encoding is specified
+        String::from_utf8(bytes.to_vec()).map_err(|e| {
+            PyUnicodeDecodeError::new_err(format!("Failed to decode file {file_name} as UTF-8: {e}"))
+        })
+    }
+}
+
+/// The names of the entries directly within `dir`, derived from a set of full
+/// file paths using the '/' convention. Shared by the filesystems that are
+/// backed by an in-memory path map ([`FakeBasicFileSystem`],
+/// [`ArchiveBasicFileSystem`]).
+fn listdir_from_paths<'a>(paths: impl Iterator<Item = &'a String>, dir: &str) -> Vec<String> {
+    let prefix = format!("{}/", dir.trim_end_matches('/'));
+    let mut names: BTreeSet<String> = BTreeSet::new();
+    for path in paths {
+        if let Some(rest) = path.strip_prefix(&prefix) {
+            let first = rest.split('/').next().unwrap_or("");
+            if !first.is_empty() {
+                names.insert(first.to_string());
+            }
+        }
+    }
+    names.into_iter().collect()
+}
+
+/// `os.walk`-style recursion over a set of full file paths using the '/'
+/// convention, reconstructing the directory tree each file implies.
+fn walk_from_paths<'a>(
+    paths: impl Iterator<Item = &'a String>,
+    root: &str,
+) -> Vec<(String, Vec<String>, Vec<String>)> {
+    let root = root.trim_end_matches('/');
+    let root_prefix = format!("{root}/");
+    let mut tree: BTreeMap<String, (BTreeSet<String>, BTreeSet<String>)> = BTreeMap::new();
+    for path in paths {
+        if path != root && !path.starts_with(&root_prefix) {
+            continue;
+        }
+        let components: Vec<&str> = path.split('/').collect();
+        for i in 0..components.len() {
+            let dir_path = components[0..i].join("/");
+            if dir_path != root && !dir_path.starts_with(&root_prefix) {
+                continue;
+            }
+            let entry = tree.entry(dir_path).or_default();
+            if i == components.len() - 1 {
+                entry.1.insert(components[i].to_string());
+            } else {
+                entry.0.insert(components[i].to_string());
+            }
+        }
+    }
+
+    tree.into_iter()
+        .map(|(dir_path, (subdirs, files))| {
+            (
+                dir_path,
+                subdirs.into_iter().collect(),
+                files.into_iter().collect(),
+            )
+        })
+        .collect()
 }
 
 #[derive(Clone)]
@@ -35,16 +194,26 @@ impl FileSystem for RealBasicFileSystem {
         std::path::MAIN_SEPARATOR.to_string()
     }
 
+    fn altsep(&self) -> Option<String> {
+        // Windows accepts '/' as well as '\'; POSIX has no alternate separator.
+        if std::path::MAIN_SEPARATOR == '\\' {
+            Some("/".to_string())
+        } else {
+            None
+        }
+    }
+
     fn join(&self, components: Vec<String>) -> String {
         let mut path = PathBuf::new();
         for component in components {
-            path.push(component);
+            path.push(self.normalize_separators(&component));
         }
         path.to_str().unwrap().to_string()
     }
 
     fn split(&self, file_name: &str) -> (String, String) {
-        let path = Path::new(file_name);
+        let file_name = self.normalize_separators(file_name);
+        let path = Path::new(&file_name);
 
         // Get the "tail" part (the file name or last directory)
         let tail = match path.file_name() {
@@ -69,54 +238,76 @@ impl FileSystem for RealBasicFileSystem {
     }
 
     fn read(&self, file_name: &str) -> PyResult<String> {
-        // Python files are assumed UTF-8 by default (PEP 686), but they can specify an alternative
-        // encoding, which we need to take into account here.
-        // See https://peps.python.org/pep-0263/
-
-        // This method was authored primarily by an LLM.
-
         let path = Path::new(file_name);
         let bytes = fs::read(path).map_err(|e| {
             PyFileNotFoundError::new_err(format!("Failed to read file {file_name}: {e}"))
         })?;
+        decode_python_source(&bytes, file_name)
+    }
 
-        let s = String::from_utf8_lossy(&bytes);
-        let encoding_re = Regex::new(r"^[ \t\f]*#.*?coding[:=][ \t]*([-_.a-zA-Z0-9]+)").unwrap();
+    fn listdir(&self, dir: &str) -> PyResult<Vec<String>> {
+        let entries = fs::read_dir(dir).map_err(|e| {
+            PyFileNotFoundError::new_err(format!("Failed to list directory {dir}: {e}"))
+        })?;
+        let mut names = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| {
+                PyFileNotFoundError::new_err(format!("Failed to read an entry in {dir}: {e}"))
+            })?;
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+        names.sort();
+        Ok(names)
+    }
 
-        let mut detected_encoding: Option<String> = None;
+    fn walk(&self, root: &str) -> PyResult<Vec<(String, Vec<String>, Vec<String>)>> {
+        let mut results = Vec::new();
+        // Canonical paths of directories we've already descended into, so a
+        // symlink pointing back up the tree can't trap us in an infinite loop.
+        let mut visited: HashSet<PathBuf> = HashSet::new();
+        let mut pending = vec![root.to_string()];
 
-        // Coding specification needs to be in the first two lines, or it's ignored.
-        for line in s.lines().take(2) {
-            if let Some(captures) = encoding_re.captures(line)
-                && let Some(encoding_name) = captures.get(1) {
-                    detected_encoding = Some(encoding_name.as_str().to_string());
-                    break;
-                }
-        }
+        while let Some(current) = pending.pop() {
+            // A directory that can't be canonicalized (e.g. a broken symlink)
+            // is simply skipped, as is one we've already visited.
+            let canonical = match fs::canonicalize(&current) {
+                Ok(canonical) => canonical,
+                Err(_) => continue,
+            };
+            if !visited.insert(canonical) {
+                continue;
+            }
 
-        if let Some(enc_name) = detected_encoding {
-            let encoding =
-                encoding_rs::Encoding::for_label(enc_name.as_bytes()).ok_or_else(|| {
-                    PyUnicodeDecodeError::new_err(format!(
-                        "Failed to decode file {file_name} (unknown encoding '{enc_name}')"
+            let entries = fs::read_dir(&current).map_err(|e| {
+                PyFileNotFoundError::new_err(format!("Failed to walk directory {current}: {e}"))
+            })?;
+            let mut subdirs = Vec::new();
+            let mut files = Vec::new();
+            for entry in entries {
+                let entry = entry.map_err(|e| {
+                    PyFileNotFoundError::new_err(format!(
+                        "Failed to read an entry in {current}: {e}"
                     ))
                 })?;
-            let (decoded_s, _, had_errors) = encoding.decode(&bytes);
-            if had_errors {
-                Err(PyUnicodeDecodeError::new_err(format!(
-                    "Failed to decode file {file_name} with encoding '{enc_name}'"
-                )))
-            } else {
-                Ok(decoded_s.into_owned())
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if entry.path().is_dir() {
+                    subdirs.push(name);
+                } else {
+                    files.push(name);
+                }
             }
-        } else {
-            // Default to UTF-8 if no encoding is specified
-            String::from_utf8(bytes).map_err(|e| {
-                PyUnicodeDecodeError::new_err(format!(
-                    "Failed to decode file {file_name} as UTF-8: {e}"
-                ))
-            })
+            subdirs.sort();
+            files.sort();
+
+            // Queue child directories for descent. Push in reverse so the queue
+            // pops them in sorted order.
+            for subdir in subdirs.iter().rev() {
+                pending.push(self.join(vec![current.clone(), subdir.clone()]));
+            }
+            results.push((current, subdirs, files));
         }
+
+        Ok(results)
     }
 }
 
@@ -150,6 +341,28 @@ impl PyRealBasicFileSystem {
     fn read(&self, file_name: &str) -> PyResult<String> {
         self.inner.read(file_name)
     }
+
+    fn listdir(&self, dir: &str) -> PyResult<Vec<String>> {
+        self.inner.listdir(dir)
+    }
+
+    fn walk(&self, root: &str) -> PyResult<Vec<(String, Vec<String>, Vec<String>)>> {
+        self.inner.walk(root)
+    }
+
+    #[getter]
+    fn extsep(&self) -> String {
+        self.inner.extsep()
+    }
+
+    #[getter]
+    fn altsep(&self) -> Option<String> {
+        self.inner.altsep()
+    }
+
+    fn splitext(&self, name: &str) -> (String, String) {
+        self.inner.splitext(name)
+    }
 }
 
 type FileSystemContents = HashMap<String, String>;
@@ -189,16 +402,23 @@ impl FileSystem for FakeBasicFileSystem {
         "/".to_string()
     }
 
+    fn altsep(&self) -> Option<String> {
+        // A '/'-based filesystem may still be handed Windows-style paths.
+        Some("\\".to_string())
+    }
+
     fn join(&self, components: Vec<String>) -> String {
         let sep = self.sep();
         components
             .into_iter()
-            .map(|c| c.trim_end_matches(&sep).to_string())
+            .map(|c| self.normalize_separators(&c).trim_end_matches(&sep).to_string())
             .collect::<Vec<String>>()
             .join(&sep)
     }
 
     fn split(&self, file_name: &str) -> (String, String) {
+        let file_name = self.normalize_separators(file_name);
+        let file_name = file_name.as_str();
         let path;
         let head;
         let tail;
@@ -227,6 +447,14 @@ impl FileSystem for FakeBasicFileSystem {
             None => Err(PyFileNotFoundError::new_err("")),
         }
     }
+
+    fn listdir(&self, dir: &str) -> PyResult<Vec<String>> {
+        Ok(listdir_from_paths(self.contents.keys(), dir))
+    }
+
+    fn walk(&self, root: &str) -> PyResult<Vec<(String, Vec<String>, Vec<String>)>> {
+        Ok(walk_from_paths(self.contents.keys(), root))
+    }
 }
 
 #[pymethods]
@@ -261,7 +489,29 @@ impl PyFakeBasicFileSystem {
     fn read(&self, file_name: &str) -> PyResult<String> {
         self.inner.read(file_name)
     }
-    
+
+    fn listdir(&self, dir: &str) -> PyResult<Vec<String>> {
+        self.inner.listdir(dir)
+    }
+
+    fn walk(&self, root: &str) -> PyResult<Vec<(String, Vec<String>, Vec<String>)>> {
+        self.inner.walk(root)
+    }
+
+    #[getter]
+    fn extsep(&self) -> String {
+        self.inner.extsep()
+    }
+
+    #[getter]
+    fn altsep(&self) -> Option<String> {
+        self.inner.altsep()
+    }
+
+    fn splitext(&self, name: &str) -> (String, String) {
+        self.inner.splitext(name)
+    }
+
     // Temporary workaround method for Python tests.
     fn convert_to_basic(&self) -> PyResult<Self> {
         Ok(PyFakeBasicFileSystem {
@@ -270,6 +520,275 @@ impl PyFakeBasicFileSystem {
     }
 }
 
+/// A [`FileSystem`] that serves `.py` sources straight out of a `.tar`,
+/// `.tar.gz`, or `.zip` archive, so an installed wheel or sdist can be analyzed
+/// without first unpacking it to disk.
+///
+/// The archive is scanned once on construction into an index mapping each
+/// normalized internal path to its raw (already-decompressed) bytes. Paths use
+/// the archive's internal '/' convention, mirroring [`FakeBasicFileSystem`].
+#[derive(Clone)]
+pub struct ArchiveBasicFileSystem {
+    entries: HashMap<String, Vec<u8>>,
+}
+
+/// The container format of an archive, either sniffed from a path's extension
+/// or supplied by a caller that already knows it (e.g. from a `zip://` /
+/// `tar://` scheme).
+#[derive(Clone, Copy)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Infer the format from a path's extension, treating wheels (`.whl`) and
+    /// eggs (`.egg`) as the zips they are. Returns `None` for an unrecognised
+    /// extension.
+    fn from_path(path: &str) -> Option<ArchiveFormat> {
+        let lower = path.to_ascii_lowercase();
+        if lower.ends_with(".zip") || lower.ends_with(".whl") || lower.ends_with(".egg") {
+            Some(ArchiveFormat::Zip)
+        } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if lower.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+impl ArchiveBasicFileSystem {
+    /// Open an archive, inferring the format from its extension. Wheels (`.whl`)
+    /// and eggs (`.egg`) are zip archives and are opened as such.
+    pub fn open(archive_path: &str) -> PyResult<Self> {
+        let format = ArchiveFormat::from_path(archive_path).ok_or_else(|| {
+            PyValueError::new_err(format!("Unsupported archive format: {archive_path}"))
+        })?;
+        Self::open_with_format(archive_path, format)
+    }
+
+    /// Open an archive whose container format is already known, skipping the
+    /// extension sniff. Used when the caller derived the format another way,
+    /// e.g. from a `zip://` / `tar://` scheme.
+    pub fn open_with_format(archive_path: &str, format: ArchiveFormat) -> PyResult<Self> {
+        let entries = match format {
+            ArchiveFormat::Zip => Self::index_zip(archive_path)?,
+            ArchiveFormat::TarGz => {
+                let file = fs::File::open(archive_path).map_err(|e| {
+                    PyFileNotFoundError::new_err(format!(
+                        "Failed to open archive {archive_path}: {e}"
+                    ))
+                })?;
+                Self::index_tar(flate2::read::GzDecoder::new(file))?
+            }
+            ArchiveFormat::Tar => {
+                let file = fs::File::open(archive_path).map_err(|e| {
+                    PyFileNotFoundError::new_err(format!(
+                        "Failed to open archive {archive_path}: {e}"
+                    ))
+                })?;
+                Self::index_tar(file)?
+            }
+        };
+        Ok(ArchiveBasicFileSystem { entries })
+    }
+
+    fn index_zip(archive_path: &str) -> PyResult<HashMap<String, Vec<u8>>> {
+        use std::io::Read;
+
+        let file = fs::File::open(archive_path).map_err(|e| {
+            PyFileNotFoundError::new_err(format!("Failed to open archive {archive_path}: {e}"))
+        })?;
+        let mut zip = zip::ZipArchive::new(file)
+            .map_err(|e| PyValueError::new_err(format!("Failed to read archive: {e}")))?;
+
+        let mut entries = HashMap::new();
+        for i in 0..zip.len() {
+            let mut entry = zip
+                .by_index(i)
+                .map_err(|e| PyValueError::new_err(format!("Failed to read archive entry: {e}")))?;
+            if entry.is_dir() {
+                continue;
+            }
+            let Some(name) = sanitize_entry_path(entry.name())? else {
+                continue;
+            };
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).map_err(|e| {
+                PyValueError::new_err(format!("Failed to read archive entry {name}: {e}"))
+            })?;
+            entries.insert(name, bytes);
+        }
+        Ok(entries)
+    }
+
+    fn index_tar<R: std::io::Read>(reader: R) -> PyResult<HashMap<String, Vec<u8>>> {
+        use std::io::Read;
+
+        let mut archive = tar::Archive::new(reader);
+        let mut entries = HashMap::new();
+        for entry in archive
+            .entries()
+            .map_err(|e| PyValueError::new_err(format!("Failed to read archive: {e}")))?
+        {
+            let mut entry =
+                entry.map_err(|e| PyValueError::new_err(format!("Failed to read archive entry: {e}")))?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let path = entry
+                .path()
+                .map_err(|e| PyValueError::new_err(format!("Invalid archive entry path: {e}")))?
+                .to_string_lossy()
+                .into_owned();
+            let Some(name) = sanitize_entry_path(&path)? else {
+                continue;
+            };
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).map_err(|e| {
+                PyValueError::new_err(format!("Failed to read archive entry {name}: {e}"))
+            })?;
+            entries.insert(name, bytes);
+        }
+        Ok(entries)
+    }
+}
+
+/// Normalize an archive entry path to the internal '/' convention, rejecting
+/// any attempt to escape the logical root. Returns `Ok(None)` for a purely
+/// structural path (e.g. a `./` prefix that collapses to nothing).
+fn sanitize_entry_path(raw: &str) -> PyResult<Option<String>> {
+    let mut components: Vec<&str> = Vec::new();
+    for component in raw.replace('\\', "/").split('/') {
+        match component {
+            "" | "." => continue,
+            ".." => {
+                return Err(PyValueError::new_err(format!(
+                    "Archive entry escapes the archive root: {raw}"
+                )));
+            }
+            other => components.push(other),
+        }
+    }
+    if components.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(components.join("/")))
+    }
+}
+
+impl FileSystem for ArchiveBasicFileSystem {
+    fn sep(&self) -> String {
+        "/".to_string()
+    }
+
+    fn altsep(&self) -> Option<String> {
+        Some("\\".to_string())
+    }
+
+    fn join(&self, components: Vec<String>) -> String {
+        let sep = self.sep();
+        components
+            .into_iter()
+            .map(|c| self.normalize_separators(&c).trim_end_matches(&sep).to_string())
+            .collect::<Vec<String>>()
+            .join(&sep)
+    }
+
+    fn split(&self, file_name: &str) -> (String, String) {
+        let file_name = self.normalize_separators(file_name);
+        match file_name.rsplit_once('/') {
+            Some((head, tail)) => (head.to_string(), tail.to_string()),
+            None => (String::new(), file_name.clone()),
+        }
+    }
+
+    fn exists(&self, file_name: &str) -> bool {
+        self.entries.contains_key(file_name)
+    }
+
+    fn read(&self, file_name: &str) -> PyResult<String> {
+        match self.entries.get(file_name) {
+            Some(bytes) => decode_python_source(bytes, file_name),
+            None => Err(PyFileNotFoundError::new_err(format!(
+                "No such entry in archive: {file_name}"
+            ))),
+        }
+    }
+
+    fn listdir(&self, dir: &str) -> PyResult<Vec<String>> {
+        Ok(listdir_from_paths(self.entries.keys(), dir))
+    }
+
+    fn walk(&self, root: &str) -> PyResult<Vec<(String, Vec<String>, Vec<String>)>> {
+        Ok(walk_from_paths(self.entries.keys(), root))
+    }
+}
+
+/// Build the appropriate [`FileSystem`] backend for a path-or-URL string,
+/// inferring the backend from a scheme prefix (the way fsspec infers storage
+/// options):
+///
+/// - `file://…` or a bare local path → [`RealBasicFileSystem`]
+/// - `zip://…!/inner/path` / `tar://…!/inner/path` → [`ArchiveBasicFileSystem`]
+/// - `memory://…` → an empty [`FakeBasicFileSystem`]
+///
+/// Returns the backend together with the in-backend path to hand to `read`,
+/// e.g. `build_filesystem("zip:///pkgs/foo.whl!grimp/__init__.py")` yields an
+/// archive over `/pkgs/foo.whl` and the path `grimp/__init__.py`.
+pub fn build_filesystem(location: &str) -> PyResult<(Box<dyn FileSystem + Send + Sync>, String)> {
+    let (scheme, remainder) = split_scheme(location);
+    match scheme.as_deref() {
+        None | Some("file") => Ok((Box::new(RealBasicFileSystem {}), remainder)),
+        Some("memory") => Ok((Box::new(FakeBasicFileSystem::new(None, None)?), remainder)),
+        Some(scheme @ ("zip" | "tar")) => {
+            // Split the archive location from the path inside it on the first '!'.
+            let (archive_path, inner_path) = match remainder.split_once('!') {
+                Some((archive, inner)) => {
+                    (archive.to_string(), inner.trim_start_matches('/').to_string())
+                }
+                None => (remainder, String::new()),
+            };
+            // The scheme already fixes the container format, so don't re-sniff by
+            // extension — that would reject e.g. `zip:///pkgs/foo.whl`. For `tar`,
+            // still distinguish plain from gzipped by extension.
+            let format = if scheme == "zip" {
+                ArchiveFormat::Zip
+            } else {
+                let lower = archive_path.to_ascii_lowercase();
+                if lower.ends_with(".gz") || lower.ends_with(".tgz") {
+                    ArchiveFormat::TarGz
+                } else {
+                    ArchiveFormat::Tar
+                }
+            };
+            Ok((
+                Box::new(ArchiveBasicFileSystem::open_with_format(
+                    &archive_path,
+                    format,
+                )?),
+                inner_path,
+            ))
+        }
+        Some(other) => Err(PyValueError::new_err(format!(
+            "Unsupported filesystem scheme: {other}"
+        ))),
+    }
+}
+
+/// urlsplit-style helper: split off a `scheme://` prefix, returning the
+/// lowercased scheme (if present) and the remainder. A bare local path has no
+/// scheme and is returned unchanged.
+fn split_scheme(location: &str) -> (Option<String>, String) {
+    match location.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme.to_ascii_lowercase()), rest.to_string()),
+        None => (None, location.to_string()),
+    }
+}
+
 /// Parses an indented string representing a file system structure
 /// into a HashMap where keys are full file paths.
 /// See tests.adaptors.filesystem.FakeFileSystem for the API.
@@ -355,3 +874,220 @@ pub fn parse_indented_file_system_string(file_system_string: &str) -> HashMap<St
 
     file_paths_map
 }
+
+/// A [`FileSystem`] decorator that memoizes `read` results, so an unchanged
+/// file isn't re-read and re-decoded across repeated graph builds.
+///
+/// Each cached entry is keyed on a cheap stat signature (size + last-modified
+/// time); a `read` first re-stats the file and returns the cached decoded
+/// string when the signature is unchanged, otherwise it reads through, recomputes
+/// a fast non-cryptographic content hash, and refreshes the entry. The
+/// signatures can be persisted with [`CachingFileSystem::dump`] /
+/// [`CachingFileSystem::load`] so a later process can tell, from the stat
+/// signature alone, which files are unchanged — mirroring how version-control
+/// keys its caches on a cheap signature and recomputes only on mismatch.
+///
+/// The persisted entries carry their decoded contents as well as the signature,
+/// so a reloaded cache can serve an unchanged file without reading it back
+/// through the inner filesystem at all.
+pub struct CachingFileSystem {
+    inner: Box<dyn FileSystem + Send + Sync>,
+    cache: Mutex<HashMap<String, CacheEntry>>,
+}
+
+struct CacheEntry {
+    signature: StatSignature,
+    /// The decoded source. Populated both for entries read this run and for
+    /// those reloaded via [`CachingFileSystem::load`], so a matching file can be
+    /// served without touching the inner filesystem. Absent only for an entry
+    /// whose content isn't known yet.
+    decoded: Option<String>,
+}
+
+/// The cheap identity of a file: its size, last-modified time, and a content
+/// hash. [`CachingFileSystem::dump`] persists it, together with the decoded
+/// contents, as a `(size, mtime, hash, decoded)` tuple.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct StatSignature {
+    size: u64,
+    mtime: i64,
+    hash: u64,
+}
+
+impl CachingFileSystem {
+    pub fn new(inner: Box<dyn FileSystem + Send + Sync>) -> Self {
+        CachingFileSystem {
+            inner,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Serialize the `{path -> (size, mtime, hash, decoded)}` map so it can be
+    /// reloaded in a later run. The decoded source travels with each signature
+    /// so a reloaded entry can be served without reading through again.
+    pub fn dump(&self) -> String {
+        let cache = self.cache.lock().unwrap();
+        let entries: HashMap<&str, (u64, i64, u64, Option<String>)> = cache
+            .iter()
+            .map(|(path, entry)| {
+                (
+                    path.as_str(),
+                    (
+                        entry.signature.size,
+                        entry.signature.mtime,
+                        entry.signature.hash,
+                        entry.decoded.clone(),
+                    ),
+                )
+            })
+            .collect();
+        serde_json::to_string(&entries).expect("Failed to serialize cache entries")
+    }
+
+    /// Reload a map produced by [`CachingFileSystem::dump`]. The decoded
+    /// contents are restored alongside each signature, so a `read` of an
+    /// unchanged file is served straight from the cache.
+    pub fn load(&self, serialized: &str) -> PyResult<()> {
+        let entries: HashMap<String, (u64, i64, u64, Option<String>)> =
+            serde_json::from_str(serialized)
+                .map_err(|e| PyValueError::new_err(format!("Failed to load cache entries: {e}")))?;
+        let mut cache = self.cache.lock().unwrap();
+        for (path, (size, mtime, hash, decoded)) in entries {
+            cache.insert(
+                path,
+                CacheEntry {
+                    signature: StatSignature { size, mtime, hash },
+                    decoded,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Cheaply stat a file for its (size, mtime), if the backing store is a real
+    /// filesystem. Archive/fake backends have no meaningful mtime, so this
+    /// returns `None` for them and `read` trusts any cached decoding.
+    fn stat(&self, file_name: &str) -> Option<(u64, i64)> {
+        let metadata = fs::metadata(file_name).ok()?;
+        let mtime = metadata
+            .modified()
+            .ok()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Some((metadata.len(), mtime))
+    }
+}
+
+impl FileSystem for CachingFileSystem {
+    fn sep(&self) -> String {
+        self.inner.sep()
+    }
+
+    fn join(&self, components: Vec<String>) -> String {
+        self.inner.join(components)
+    }
+
+    fn split(&self, file_name: &str) -> (String, String) {
+        self.inner.split(file_name)
+    }
+
+    fn exists(&self, file_name: &str) -> bool {
+        self.inner.exists(file_name)
+    }
+
+    fn read(&self, file_name: &str) -> PyResult<String> {
+        let stat = self.stat(file_name);
+
+        // Fast path: serve a cached decoding without touching the inner
+        // filesystem. On a stattable backend the cached entry is valid only
+        // while the file's size and mtime are unchanged; on a non-stattable one
+        // (archive/fake) there's nothing cheap to invalidate against, so a
+        // cached decoding — including one just reloaded via `load` — is trusted.
+        {
+            let cache = self.cache.lock().unwrap();
+            if let Some(entry) = cache.get(file_name)
+                && let Some(decoded) = &entry.decoded
+            {
+                let fresh = match stat {
+                    Some((size, mtime)) => {
+                        entry.signature.size == size && entry.signature.mtime == mtime
+                    }
+                    None => true,
+                };
+                if fresh {
+                    return Ok(decoded.clone());
+                }
+            }
+        }
+
+        // Miss: read through, recompute the hash, and refresh the entry.
+        let decoded = self.inner.read(file_name)?;
+        let (size, mtime) = stat.unwrap_or((decoded.len() as u64, 0));
+        let signature = StatSignature {
+            size,
+            mtime,
+            hash: content_hash(decoded.as_bytes()),
+        };
+        self.cache.lock().unwrap().insert(
+            file_name.to_string(),
+            CacheEntry {
+                signature,
+                decoded: Some(decoded.clone()),
+            },
+        );
+        Ok(decoded)
+    }
+
+    fn listdir(&self, dir: &str) -> PyResult<Vec<String>> {
+        self.inner.listdir(dir)
+    }
+
+    fn walk(&self, root: &str) -> PyResult<Vec<(String, Vec<String>, Vec<String>)>> {
+        self.inner.walk(root)
+    }
+}
+
+/// A fast, non-cryptographic hash of a file's decoded contents, stored in each
+/// cached signature and persisted by [`CachingFileSystem::dump`]. `read` decides
+/// freshness from size and mtime alone and does not compare this hash.
+fn content_hash(bytes: &[u8]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_with(path: &str, contents: &str) -> Box<dyn FileSystem + Send + Sync> {
+        let mut map = HashMap::new();
+        map.insert(path.to_string(), contents.to_string());
+        Box::new(FakeBasicFileSystem::new(None, Some(map)).unwrap())
+    }
+
+    #[test]
+    fn test_dump_includes_decoded_contents() {
+        let fs = CachingFileSystem::new(fake_with("pkg/a.py", "import os"));
+        assert_eq!(fs.read("pkg/a.py").unwrap(), "import os");
+        assert!(fs.dump().contains("import os"));
+    }
+
+    #[test]
+    fn test_read_after_load_does_not_hit_inner_filesystem() {
+        // Prime a cache over a filesystem that holds the file, then dump it.
+        let primed = CachingFileSystem::new(fake_with("pkg/a.py", "import os"));
+        primed.read("pkg/a.py").unwrap();
+        let dumped = primed.dump();
+
+        // Reload the dump over an *empty* filesystem. A read that reached the
+        // inner filesystem would fail to find the file, so serving the contents
+        // from the reloaded cache proves the inner filesystem was not consulted.
+        let reloaded =
+            CachingFileSystem::new(Box::new(FakeBasicFileSystem::new(None, None).unwrap()));
+        reloaded.load(&dumped).unwrap();
+        assert_eq!(reloaded.read("pkg/a.py").unwrap(), "import os");
+    }
+}
@@ -113,6 +113,134 @@ impl Graph {
         Ok(chains)
     }
 
+    /// Enumerate *all* simple import chains from `importer` to `imported`, i.e.
+    /// every path that visits no module twice, optionally bounded to at most
+    /// `max_length` hops. Unlike [`Graph::find_shortest_chains`], which returns
+    /// an edge-disjoint set of shortest chains, this answers "show me every
+    /// path no longer than N".
+    ///
+    /// Results are sorted so repeated runs are reproducible.
+    pub fn find_all_chains(
+        &self,
+        importer: ModuleToken,
+        imported: ModuleToken,
+        as_packages: bool,
+        max_length: Option<usize>,
+    ) -> GrimpResult<Vec<Vec<ModuleToken>>> {
+        let (from_modules, to_modules) = if as_packages {
+            (
+                importer.conv::<FxHashSet<_>>().with_descendants(self),
+                imported.conv::<FxHashSet<_>>().with_descendants(self),
+            )
+        } else {
+            (
+                importer.conv::<FxHashSet<_>>(),
+                imported.conv::<FxHashSet<_>>(),
+            )
+        };
+
+        self._find_all_chains(
+            &from_modules,
+            &to_modules,
+            &FxHashSet::default(),
+            &FxHashMap::default(),
+            max_length,
+        )
+    }
+
+    pub(crate) fn _find_all_chains(
+        &self,
+        from_modules: &FxHashSet<ModuleToken>,
+        to_modules: &FxHashSet<ModuleToken>,
+        excluded_modules: &FxHashSet<ModuleToken>,
+        excluded_imports: &FxHashMap<ModuleToken, FxHashSet<ModuleToken>>,
+        max_length: Option<usize>,
+    ) -> GrimpResult<Vec<Vec<ModuleToken>>> {
+        let mut chains: Vec<Vec<ModuleToken>> = vec![];
+        // Modules on the current DFS stack, used to keep each path simple.
+        let mut on_stack: FxHashSet<ModuleToken> = FxHashSet::default();
+        let mut path: Vec<ModuleToken> = vec![];
+
+        // Walk start modules in a fixed order for deterministic output.
+        let mut starts: Vec<ModuleToken> = from_modules.iter().copied().collect();
+        starts.sort();
+        for start in starts {
+            if excluded_modules.contains(&start) {
+                continue;
+            }
+            self._collect_chains(
+                start,
+                to_modules,
+                excluded_modules,
+                excluded_imports,
+                max_length,
+                &mut on_stack,
+                &mut path,
+                &mut chains,
+            );
+        }
+
+        chains.sort();
+        Ok(chains)
+    }
+
+    /// Bounded depth-first walk backing [`Graph::find_all_chains`]. `on_stack`
+    /// carries the modules already on the current path so cycles can't be
+    /// re-entered, and the hop count is capped at `max_length`.
+    #[allow(clippy::too_many_arguments)]
+    fn _collect_chains(
+        &self,
+        module: ModuleToken,
+        to_modules: &FxHashSet<ModuleToken>,
+        excluded_modules: &FxHashSet<ModuleToken>,
+        excluded_imports: &FxHashMap<ModuleToken, FxHashSet<ModuleToken>>,
+        max_length: Option<usize>,
+        on_stack: &mut FxHashSet<ModuleToken>,
+        path: &mut Vec<ModuleToken>,
+        chains: &mut Vec<Vec<ModuleToken>>,
+    ) {
+        path.push(module);
+        on_stack.insert(module);
+
+        // A target ends the chain; we don't walk through endpoints looking for
+        // a further one.
+        if to_modules.contains(&module) && path.len() >= 2 {
+            chains.push(path.clone());
+            path.pop();
+            on_stack.remove(&module);
+            return;
+        }
+
+        let hops = path.len() - 1;
+        let within_bound = max_length.is_none_or(|max| hops < max);
+        if within_bound && let Some(neighbours) = self.imports.get(&module) {
+            let excluded_for_module = excluded_imports.get(&module);
+            let mut neighbours: Vec<ModuleToken> = neighbours.iter().copied().collect();
+            neighbours.sort();
+            for neighbour in neighbours {
+                if on_stack.contains(&neighbour) || excluded_modules.contains(&neighbour) {
+                    continue;
+                }
+                if excluded_for_module.is_some_and(|imports| imports.contains(&neighbour)) {
+                    continue;
+                }
+                self._collect_chains(
+                    neighbour,
+                    to_modules,
+                    excluded_modules,
+                    excluded_imports,
+                    max_length,
+                    on_stack,
+                    path,
+                    chains,
+                );
+            }
+        }
+
+        path.pop();
+        on_stack.remove(&module);
+    }
+
     pub(crate) fn _find_shortest_chains(
         &self,
         from_modules: &FxHashSet<ModuleToken>,
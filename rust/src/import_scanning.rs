@@ -1,4 +1,5 @@
 /// Statically analyses some Python modules for import statements within their shared package.
+use crate::caching;
 use crate::errors::GrimpResult;
 use crate::filesystem::{FileSystem, PyFakeBasicFileSystem, PyRealBasicFileSystem};
 use crate::import_parsing;
@@ -14,10 +15,63 @@ use std::io::{self, ErrorKind};
 pub struct DirectImport {
     importer: String,
     imported: String,
+    category: ImportCategory,
+    /// Whether this import was recorded from a runtime reference (e.g.
+    /// `importlib.import_module("pkg.mod")`) rather than a static
+    /// `import`/`from ... import` statement. Only ever true when dynamic
+    /// scanning was opted into.
+    is_dynamic: bool,
+    /// The full original dotted path that was imported, before distillation to
+    /// a graph edge: `django.db.models` even when `imported` is `django`.
+    full_name: String,
+    /// The local name this import is bound to when it was aliased
+    /// (`import foo.bar as fb` / `from pkg import baz as b`), or `None` for an
+    /// unaliased import.
+    alias: Option<String>,
     line_number: usize,
     line_contents: String,
 }
 
+/// The import section an imported module belongs to.
+///
+/// These mirror the sections isort and ruff's import sorter split imports into,
+/// letting consumers tell `os`/`typing` apart from `django`/`requests`.
+#[derive(Debug, Hash, Eq, PartialEq, Clone, Copy)]
+pub enum ImportCategory {
+    /// A module belonging to one of the packages being analysed.
+    FirstParty,
+    /// A module shipped with the Python standard library.
+    StandardLibrary,
+    /// A `from __future__ import ...`.
+    Future,
+    /// Anything else: an installed distribution or namespace package.
+    ThirdParty,
+}
+
+impl ImportCategory {
+    /// The stable string used in the JSON cache and the Python value object.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportCategory::FirstParty => "first_party",
+            ImportCategory::StandardLibrary => "standard_library",
+            ImportCategory::Future => "future",
+            ImportCategory::ThirdParty => "third_party",
+        }
+    }
+
+    /// The inverse of [`ImportCategory::as_str`], used when reading the cache.
+    /// Unrecognised values fall back to third-party, matching the pre-category
+    /// behaviour where everything external was a single bucket.
+    pub fn from_str(value: &str) -> ImportCategory {
+        match value {
+            "first_party" => ImportCategory::FirstParty,
+            "standard_library" => ImportCategory::StandardLibrary,
+            "future" => ImportCategory::Future,
+            _ => ImportCategory::ThirdParty,
+        }
+    }
+}
+
 pub fn py_found_packages_to_rust(py_found_packages: &Bound<'_, PyAny>) -> HashSet<FoundPackage> {
     let py_set = py_found_packages
         .downcast::<PySet>()
@@ -76,25 +130,86 @@ pub fn get_file_system_boxed<'py>(
 /// it imports.
 #[allow(clippy::borrowed_box)]
 pub fn scan_for_imports_no_py(
-    file_system: &Box<dyn FileSystem + Send + Sync>,
+    file_system: &mut Box<dyn FileSystem + Send + Sync>,
     found_packages: &HashSet<FoundPackage>,
     include_external_packages: bool,
     modules: &HashSet<Module>,
     exclude_type_checking_imports: bool,
+    scan_dynamic_imports: bool,
+    python_version: (u8, u8),
+    cache_filename: Option<&str>,
 ) -> GrimpResult<HashMap<Module, HashSet<DirectImport>>> {
+    let all_modules = get_modules_from_found_packages(found_packages);
+    let stdlib_modules = standard_library_modules(python_version);
+
+    // The cached imports depend on the scan options as well as the source, so a
+    // run with different options must not reuse entries written under others.
+    // Fold the options into a fingerprint that invalidates the whole cache on
+    // change.
+    let config_fingerprint = format!(
+        "{}|{}|{}|{}.{}",
+        scan_dynamic_imports,
+        include_external_packages,
+        exclude_type_checking_imports,
+        python_version.0,
+        python_version.1,
+    );
+
+    // Determine each module's source filename up front; this is both what the
+    // single-module scan reads and the key the incremental cache hashes.
+    let mut module_filenames: HashMap<Module, String> = HashMap::new();
+    for module in modules {
+        let found_package = _lookup_found_package_for_module(module, found_packages);
+        let filename = _determine_module_filename(module, found_package, &*file_system).unwrap();
+        module_filenames.insert(module.clone(), filename);
+    }
+
+    // Consult the incremental cache: reuse modules whose source hash is
+    // unchanged, and only rescan the dirty ones. A missing or stale-format
+    // cache falls back to scanning everything.
     let mut imports_by_module = HashMap::new();
+    let modules_to_scan: HashSet<Module> = match cache_filename {
+        Some(filename) => match caching::read_cache(
+            filename,
+            &module_filenames,
+            &config_fingerprint,
+            &*file_system,
+        ) {
+            Ok(outcome) => {
+                imports_by_module.extend(outcome.imports_by_module);
+                outcome.rescanned
+            }
+            Err(_) => modules.clone(),
+        },
+        None => modules.clone(),
+    };
 
-    for module in modules {
+    for module in &modules_to_scan {
         let imports_for_module = scan_for_imports_no_py_single_module(
             module,
-            file_system,
+            &*file_system,
             found_packages,
-            &get_modules_from_found_packages(found_packages),
+            &all_modules,
             include_external_packages,
             exclude_type_checking_imports,
+            scan_dynamic_imports,
+            &stdlib_modules,
         )?;
         imports_by_module.insert(module.clone(), imports_for_module);
     }
+
+    // Persist the merged result so the next run can reuse the unchanged modules.
+    if let Some(filename) = cache_filename {
+        caching::write_cache(
+            filename,
+            &module_filenames,
+            &imports_by_module,
+            &config_fingerprint,
+            file_system,
+        )
+        .unwrap();
+    }
+
     Ok(imports_by_module)
 }
 
@@ -106,14 +221,19 @@ fn scan_for_imports_no_py_single_module(
     all_modules: &HashSet<Module>,
     include_external_packages: bool,
     exclude_type_checking_imports: bool,
+    scan_dynamic_imports: bool,
+    stdlib_modules: &HashSet<&'static str>,
 ) -> GrimpResult<HashSet<DirectImport>> {
     let mut imports: HashSet<DirectImport> = HashSet::new();
     let found_package_for_module = _lookup_found_package_for_module(module, found_packages);
     let module_filename =
         _determine_module_filename(module, found_package_for_module, file_system).unwrap();
     let module_contents = file_system.read(&module_filename).unwrap();
-    let imported_objects =
-        import_parsing::parse_imports_from_code(&module_contents, &module_filename)?;
+    let imported_objects = import_parsing::parse_imports_from_code(
+        &module_contents,
+        &module_filename,
+        scan_dynamic_imports,
+    )?;
 
     let is_package = _module_is_package(&module_filename, file_system);
 
@@ -134,6 +254,10 @@ fn scan_for_imports_no_py_single_module(
                 imports.insert(DirectImport {
                     importer: module.name.to_string(),
                     imported: imported_module.name.to_string(),
+                    category: ImportCategory::FirstParty,
+                    is_dynamic: imported_object.is_dynamic,
+                    full_name: imported_object_name.clone(),
+                    alias: imported_object.alias.clone(),
                     line_number: imported_object.line_number,
                     line_contents: imported_object.line_contents,
                 });
@@ -146,7 +270,11 @@ fn scan_for_imports_no_py_single_module(
                     {
                         imports.insert(DirectImport {
                             importer: module.name.to_string(),
+                            category: _categorise_external_module(&imported_module, stdlib_modules),
                             imported: imported_module,
+                            is_dynamic: imported_object.is_dynamic,
+                            full_name: imported_object_name.clone(),
+                            alias: imported_object.alias.clone(),
                             line_number: imported_object.line_number,
                             line_contents: imported_object.line_contents,
                         });
@@ -174,6 +302,18 @@ pub fn to_py_direct_imports<'a>(
         let kwargs = PyDict::new(py);
         kwargs.set_item("importer", &importer).unwrap();
         kwargs.set_item("imported", &imported).unwrap();
+        kwargs
+            .set_item("category", rust_import.category.as_str())
+            .unwrap();
+        kwargs
+            .set_item("is_dynamic", rust_import.is_dynamic)
+            .unwrap();
+        kwargs
+            .set_item("full_name", &rust_import.full_name)
+            .unwrap();
+        kwargs
+            .set_item("alias", rust_import.alias.as_deref())
+            .unwrap();
         kwargs
             .set_item("line_number", rust_import.line_number)
             .unwrap();
@@ -246,7 +386,7 @@ fn _get_absolute_imported_object_name(
 ) -> String {
     let leading_dots_count = count_leading_dots(imported_object_name);
     if leading_dots_count == 0 {
-        return imported_object_name.to_string();
+        return _strip_wildcard(imported_object_name);
     }
     let imported_object_name_base: String;
     if is_package {
@@ -261,11 +401,50 @@ fn _get_absolute_imported_object_name(
         imported_object_name_base = parts[0..parts.len() - leading_dots_count].join(".");
     }
 
-    format!(
-        "{}.{}",
-        imported_object_name_base,
-        &imported_object_name[leading_dots_count..]
-    )
+    // A name made only of dots leaves nothing to their right, so the tail is
+    // empty; guard against emitting a name with a dangling dot. (`from . import *`
+    // arrives as `.*`, whose `*` tail `_strip_wildcard` removes below.)
+    let tail = &imported_object_name[leading_dots_count..];
+    if tail.is_empty() {
+        return _strip_wildcard(&imported_object_name_base);
+    }
+
+    _strip_wildcard(&format!("{imported_object_name_base}.{tail}"))
+}
+
+/// Reduce a wildcard import to the package/module it depends on.
+///
+/// `from .subpkg import *` binds every name in `subpkg`, so the edge we care
+/// about is simply a dependency on `subpkg`. Drop a trailing `*` object (and the
+/// dot that separated it) rather than leaving a dangling `.` or an empty tail.
+fn _strip_wildcard(imported_object_name: &str) -> String {
+    if imported_object_name == "*" {
+        return String::new();
+    }
+    imported_object_name
+        .strip_suffix(".*")
+        .unwrap_or(imported_object_name)
+        .to_string()
+}
+
+/// Assign an external module to an import section, the way isort/ruff would.
+///
+/// Only the root component matters: `os.path` is standard-library because `os`
+/// is, and `django.db.models` is third-party because `django` isn't shipped
+/// with CPython. First-party modules never reach here; they're resolved as
+/// internal imports before distillation.
+fn _categorise_external_module(
+    module_name: &str,
+    stdlib_modules: &HashSet<&'static str>,
+) -> ImportCategory {
+    let module_root = module_name.split('.').next().unwrap();
+    if module_root == "__future__" {
+        ImportCategory::Future
+    } else if stdlib_modules.contains(module_root) {
+        ImportCategory::StandardLibrary
+    } else {
+        ImportCategory::ThirdParty
+    }
 }
 
 fn _get_internal_module(
@@ -351,3 +530,156 @@ fn _distill_external_module(
         Some(module_name.split('.').next().unwrap().to_string())
     }
 }
+
+/// The set of top-level standard-library module names for the target Python.
+///
+/// This stands in for `sys.stdlib_module_names` on the interpreter being
+/// analysed. We can't call into that interpreter (the analysed code may target a
+/// different version than the one running grimp), so we ship the names and key
+/// them by version, layering the per-version additions and removals on top of a
+/// shared base set. The shipped list is a subset of that attribute: private
+/// underscore-prefixed modules (other than `_thread`), internal helpers such as
+/// `posixpath`/`ntpath`, and modules removed before 3.12 such as `imp` are not
+/// listed, so imports of them are categorised as third-party.
+fn standard_library_modules(python_version: (u8, u8)) -> HashSet<&'static str> {
+    // Top-level names present across all supported 3.x versions.
+    let mut modules: HashSet<&'static str> = BASE_STDLIB_MODULE_NAMES.iter().copied().collect();
+
+    let (major, minor) = python_version;
+    if major != 3 {
+        return modules;
+    }
+
+    // `distutils` was removed in 3.12 (PEP 632).
+    if minor < 12 {
+        modules.insert("distutils");
+    }
+    // The PEP 594 "dead batteries" were removed in 3.13.
+    if minor < 13 {
+        modules.extend(PEP_594_DEAD_BATTERIES.iter().copied());
+    }
+    // `lib2to3` was removed in 3.13.
+    if minor < 13 {
+        modules.insert("lib2to3");
+    }
+    // `tomllib` was added in 3.11 (PEP 680).
+    if minor >= 11 {
+        modules.insert("tomllib");
+    }
+    // `graphlib` was added in 3.9.
+    if minor >= 9 {
+        modules.insert("graphlib");
+    }
+    // `zoneinfo` was added in 3.9 (PEP 615).
+    if minor >= 9 {
+        modules.insert("zoneinfo");
+    }
+
+    modules
+}
+
+/// Top-level standard-library module names common to the supported 3.x range.
+/// Version-specific additions and removals are applied in
+/// [`standard_library_modules`].
+const BASE_STDLIB_MODULE_NAMES: &[&str] = &[
+    "__future__", "_thread", "abc", "argparse", "array", "ast", "asyncio", "atexit",
+    "base64", "bdb", "binascii", "bisect", "builtins", "bz2", "cProfile", "calendar",
+    "cmath", "cmd", "code", "codecs", "codeop", "collections", "colorsys",
+    "compileall", "concurrent", "configparser", "contextlib", "contextvars", "copy", "copyreg",
+    "csv", "ctypes", "curses", "dataclasses", "datetime", "dbm", "decimal", "difflib",
+    "dis", "doctest", "email", "encodings", "ensurepip", "enum", "errno", "faulthandler",
+    "fcntl", "filecmp", "fileinput", "fnmatch", "fractions", "ftplib", "functools", "gc",
+    "getopt", "getpass", "gettext", "glob", "grp", "gzip", "hashlib", "heapq", "hmac", "html",
+    "http", "imaplib", "importlib", "inspect", "io", "ipaddress", "itertools", "json",
+    "keyword", "linecache", "locale", "logging", "lzma", "mailbox",
+    "marshal", "math", "mimetypes", "mmap", "modulefinder", "multiprocessing", "netrc",
+    "numbers", "operator", "optparse", "os", "pathlib", "pdb",
+    "pickle", "pickletools", "pkgutil", "platform", "plistlib", "poplib", "posix",
+    "pprint", "profile", "pstats", "pty", "pwd", "py_compile", "pyclbr", "pydoc", "queue",
+    "quopri", "random", "re", "readline", "reprlib", "resource", "runpy", "sched", "secrets",
+    "select", "selectors", "shelve", "shlex", "shutil", "signal", "site", "smtplib",
+    "socket", "socketserver", "sqlite3", "ssl", "stat", "statistics", "string",
+    "stringprep", "struct", "subprocess", "symtable", "sys", "sysconfig", "syslog",
+    "tabnanny", "tarfile", "tempfile", "termios", "test", "textwrap", "threading",
+    "time", "timeit", "tkinter", "token", "tokenize", "trace", "traceback", "tracemalloc",
+    "tty", "turtle", "turtledemo", "types", "typing", "unicodedata", "unittest", "urllib",
+    "uuid", "venv", "warnings", "wave", "weakref", "webbrowser", "winreg", "winsound", "wsgiref",
+    "xml", "xmlrpc", "zipapp", "zipfile", "zipimport", "zlib",
+];
+
+/// The PEP 594 "dead batteries": modules that shipped with the standard library
+/// through 3.12 and were removed in 3.13. Treated as standard-library names only
+/// for target versions before 3.13; on 3.13+ an import of one of these resolves
+/// as third-party, matching the interpreter's `sys.stdlib_module_names`.
+const PEP_594_DEAD_BATTERIES: &[&str] = &[
+    "aifc", "audioop", "cgi", "cgitb", "chunk", "crypt", "imghdr", "mailcap", "msilib", "nis",
+    "nntplib", "ossaudiodev", "pipes", "sndhdr", "spwd", "sunau", "telnetlib", "uu", "xdrlib",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(name: &str) -> Module {
+        Module {
+            name: name.to_string(),
+        }
+    }
+
+    // `from .pkg import *` should resolve to a clean dependency on the imported
+    // package, never leaving a dangling dot or an empty tail.
+
+    #[test]
+    fn test_wildcard_from_current_package_in_package() {
+        // `from . import *` inside a package's __init__ depends on the package.
+        let resolved = _get_absolute_imported_object_name(&module("a.b.c"), true, ".*");
+        assert_eq!(resolved, "a.b.c");
+    }
+
+    #[test]
+    fn test_wildcard_from_current_package_in_non_package() {
+        // `from . import *` inside a plain module depends on its parent package.
+        let resolved = _get_absolute_imported_object_name(&module("a.b.c"), false, ".*");
+        assert_eq!(resolved, "a.b");
+    }
+
+    #[test]
+    fn test_wildcard_from_parent_package_in_package() {
+        // `from .. import *` inside a package's __init__ depends on the parent.
+        let resolved = _get_absolute_imported_object_name(&module("a.b.c"), true, "..*");
+        assert_eq!(resolved, "a.b");
+    }
+
+    #[test]
+    fn test_wildcard_from_parent_package_in_non_package() {
+        // `from .. import *` inside a plain module depends on its grandparent.
+        let resolved = _get_absolute_imported_object_name(&module("a.b.c"), false, "..*");
+        assert_eq!(resolved, "a");
+    }
+
+    #[test]
+    fn test_wildcard_from_relative_subpackage_in_package() {
+        // `from .pkg import *` depends on the named subpackage.
+        let resolved = _get_absolute_imported_object_name(&module("a.b.c"), true, ".pkg.*");
+        assert_eq!(resolved, "a.b.c.pkg");
+    }
+
+    #[test]
+    fn test_wildcard_from_relative_subpackage_in_non_package() {
+        let resolved = _get_absolute_imported_object_name(&module("a.b.c"), false, ".pkg.*");
+        assert_eq!(resolved, "a.b.pkg");
+    }
+
+    #[test]
+    fn test_wildcard_from_absolute_package() {
+        // `from pkg import *` depends on the top-level package.
+        let resolved = _get_absolute_imported_object_name(&module("a.b.c"), false, "pkg.*");
+        assert_eq!(resolved, "pkg");
+    }
+
+    #[test]
+    fn test_non_wildcard_import_is_unchanged() {
+        let resolved = _get_absolute_imported_object_name(&module("a.b.c"), false, "os.path");
+        assert_eq!(resolved, "os.path");
+    }
+}